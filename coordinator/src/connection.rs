@@ -0,0 +1,76 @@
+use core::time::Duration;
+use std::sync::Arc;
+
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use serai_client::Serai;
+
+// How often to poll the liveness of the Serai RPC connection
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+// Cap on the reconnect backoff so a prolonged outage doesn't turn into minutes between attempts
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A handle to the Serai RPC client which is transparently rebuilt on connection loss.
+///
+/// The coordinator used to build a single `Serai` client at startup and never rebuild it, so a
+/// node restart (or any other drop) left every consumer of the handle permanently talking to a
+/// dead socket, with `handle_new_blocks` only logging and sleeping on each failed call. This
+/// instead runs a periodic health check and, on failure, reconnects with backoff and swaps the
+/// new handle in for every consumer, so the connection is restored before the next scan needs it
+/// rather than merely observed as broken.
+#[derive(Clone)]
+pub struct SeraiConnection {
+  url: String,
+  serai: Arc<RwLock<Serai>>,
+}
+
+impl SeraiConnection {
+  pub async fn new(url: String) -> SeraiConnection {
+    let serai = Arc::new(RwLock::new(Self::connect(&url).await));
+    let connection = SeraiConnection { url, serai };
+
+    tokio::spawn(connection.clone().health_check_loop());
+
+    connection
+  }
+
+  async fn connect(url: &str) -> Serai {
+    let mut backoff = Duration::from_secs(5);
+    loop {
+      match Serai::new(url).await {
+        Ok(serai) => return serai,
+        Err(e) => {
+          log::error!("couldn't connect to the Serai node: {e:?}");
+          tokio::time::sleep(backoff).await;
+          backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+        }
+      }
+    }
+  }
+
+  // A cheap call solely used to confirm the socket is still responsive
+  async fn is_alive(serai: &Serai) -> bool {
+    serai.latest_finalized_block_hash().await.is_ok()
+  }
+
+  async fn health_check_loop(self) {
+    loop {
+      tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
+
+      if Self::is_alive(&*self.serai.read().await).await {
+        continue;
+      }
+
+      log::warn!("Serai RPC connection appears dead, reconnecting");
+      let fresh = Self::connect(&self.url).await;
+      *self.serai.write().await = fresh;
+    }
+  }
+
+  /// Borrow the current handle. This is intentionally re-acquired around each use, rather than
+  /// held across `.await` points for long stretches, so a reconnect performed by the health check
+  /// is immediately visible to the next call.
+  pub async fn read(&self) -> RwLockReadGuard<'_, Serai> {
+    self.serai.read().await
+  }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use serai_db::Db;
+
+use crate::tributary::TributarySpec;
+
+const ACTIVE_TRIBUTARY_KEY: &[u8] = b"coordinator_main_active_tributaries";
+
+// Encodes/decodes the active-tributary list as length-prefixed spec blobs, since TributarySpec
+// has no serde impl of its own; this is solely MainDb's on-disk format and isn't exposed past it
+fn encode_specs(specs: &[TributarySpec]) -> Vec<u8> {
+  let mut out = Vec::new();
+  out.extend(u32::try_from(specs.len()).unwrap().to_le_bytes());
+  for spec in specs {
+    let encoded = spec.serialize();
+    out.extend(u32::try_from(encoded.len()).unwrap().to_le_bytes());
+    out.extend(encoded);
+  }
+  out
+}
+
+fn decode_specs(mut raw: &[u8]) -> Vec<TributarySpec> {
+  let mut specs = Vec::new();
+  if raw.is_empty() {
+    return specs;
+  }
+
+  let count = u32::from_le_bytes(raw[.. 4].try_into().unwrap());
+  raw = &raw[4 ..];
+  for _ in 0 .. count {
+    let len = usize::try_from(u32::from_le_bytes(raw[.. 4].try_into().unwrap())).unwrap();
+    raw = &raw[4 ..];
+    specs.push(TributarySpec::deserialize(&raw[.. len]));
+    raw = &raw[len ..];
+  }
+  specs
+}
+
+/// The coordinator's top-level database handle, covering state not scoped to any one tributary
+/// (such as which tributaries are currently active, so they can be reloaded on reboot).
+#[derive(Clone)]
+pub struct MainDb<D: Db>(pub D);
+
+impl<D: Db> MainDb<D> {
+  /// Persists `spec` so it's reloaded as an active tributary on the next `active_tributaries()`
+  /// call, including across a restart.
+  pub fn add_active_tributary(&mut self, spec: &TributarySpec) {
+    let mut specs = self.active_tributaries().1;
+    specs.push(spec.clone());
+
+    let mut txn = self.0.txn();
+    txn.put(ACTIVE_TRIBUTARY_KEY, encode_specs(&specs));
+    txn.commit();
+  }
+
+  /// Every active tributary, by genesis alongside its full spec, as persisted by
+  /// `add_active_tributary`.
+  pub fn active_tributaries(&self) -> (Vec<[u8; 32]>, Vec<TributarySpec>) {
+    let specs = match self.0.txn().get(ACTIVE_TRIBUTARY_KEY) {
+      Some(raw) => decode_specs(&raw),
+      None => vec![],
+    };
+    let geneses = specs.iter().map(TributarySpec::genesis).collect();
+    (geneses, specs)
+  }
+}
+
+/// Controls when a keyspace's writes become visible to readers of the underlying `Db`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheUpdatePolicy {
+  /// Every write is committed to the underlying `Db` immediately, so a crash can't lose it.
+  /// Used for state a restart must be able to pick back up exactly where it left off, such as a
+  /// tributary's scan cursor.
+  WriteThrough,
+  /// Writes accumulate and are only committed to the underlying `Db` when the keyspace itself is
+  /// committed. Cheaper for state that's fine to recompute (or re-derive from a scan) after a
+  /// crash, such as a latency cache.
+  OverwriteOnCommit,
+}
+
+/// A `Db` handle scoped to one tributary's genesis, so its keys can't collide with another
+/// tributary's (or the coordinator's own top-level `MainDb` state) and its entire partition can
+/// be dropped in one pass by `prune_tributary` instead of scanned key-by-key.
+pub struct TributaryDb<D: Db> {
+  db: D,
+  genesis: [u8; 32],
+  policy: CacheUpdatePolicy,
+  // Buffers writes made under `OverwriteOnCommit`, so they're only flushed to `db` as one
+  // transaction rather than one commit per write
+  pending: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl<D: Db> TributaryDb<D> {
+  /// Scopes `db` to `genesis`'s keyspace under the given cache-update policy.
+  pub fn new_in_keyspace(db: D, genesis: [u8; 32], policy: CacheUpdatePolicy) -> TributaryDb<D> {
+    TributaryDb { db, genesis, policy, pending: HashMap::new() }
+  }
+
+  fn prefixed(&self, key: &[u8]) -> Vec<u8> {
+    let mut prefixed = self.genesis.to_vec();
+    prefixed.extend(key);
+    prefixed
+  }
+
+  pub fn get(&self, key: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    let prefixed = self.prefixed(key.as_ref());
+    if let Some(value) = self.pending.get(&prefixed) {
+      return Some(value.clone());
+    }
+    self.db.txn().get(prefixed)
+  }
+
+  pub fn put(&mut self, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) {
+    let prefixed = self.prefixed(key.as_ref());
+    match self.policy {
+      CacheUpdatePolicy::WriteThrough => {
+        let mut txn = self.db.txn();
+        txn.put(prefixed, value.as_ref());
+        txn.commit();
+      }
+      CacheUpdatePolicy::OverwriteOnCommit => {
+        self.pending.insert(prefixed, value.as_ref().to_vec());
+      }
+    }
+  }
+
+  /// Flushes any writes buffered under `OverwriteOnCommit`. A no-op under `WriteThrough`, which
+  /// has nothing buffered to flush.
+  pub fn commit(&mut self) {
+    if self.pending.is_empty() {
+      return;
+    }
+    let mut txn = self.db.txn();
+    for (key, value) in self.pending.drain() {
+      txn.put(key, value);
+    }
+    txn.commit();
+  }
+
+  /// Drops every key prefixed by `genesis` in one pass, rather than requiring the caller to have
+  /// tracked which keys it wrote. Callers pass this handle's own genesis to prune its partition.
+  pub fn prune_tributary(&self, genesis: [u8; 32]) {
+    let mut txn = self.db.txn();
+    txn.del_range(genesis.to_vec() .. Self::range_end(genesis));
+    txn.commit();
+  }
+
+  // The exclusive upper bound of a genesis's key range: the same prefix incremented by one, so
+  // every key with `genesis` as its prefix (and nothing past it) falls inside [genesis, end)
+  fn range_end(mut genesis: [u8; 32]) -> Vec<u8> {
+    for byte in genesis.iter_mut().rev() {
+      if *byte == u8::MAX {
+        *byte = 0;
+        continue;
+      }
+      *byte += 1;
+      break;
+    }
+    genesis.to_vec()
+  }
+}
@@ -5,8 +5,8 @@
 
 use std::{
   sync::Arc,
-  time::Duration,
-  collections::{VecDeque, HashMap},
+  time::{Duration, Instant},
+  collections::HashMap,
 };
 
 use zeroize::Zeroizing;
@@ -14,9 +14,8 @@ use zeroize::Zeroizing;
 use ciphersuite::{group::ff::Field, Ciphersuite, Ristretto};
 
 use serai_db::{Db, MemDb};
-use serai_client::Serai;
 
-use tokio::{sync::RwLock, time::sleep};
+use tokio::sync::mpsc;
 
 use ::tributary::Tributary;
 
@@ -24,7 +23,7 @@ mod tributary;
 use crate::tributary::{TributarySpec, Transaction};
 
 mod db;
-use db::MainDb;
+use db::{MainDb, TributaryDb, CacheUpdatePolicy};
 
 mod p2p;
 pub use p2p::*;
@@ -34,14 +33,90 @@ use processor::Processor;
 
 mod substrate;
 
+mod connection;
+use connection::SeraiConnection;
+
+mod metrics;
+use metrics::Metrics;
+
 #[cfg(test)]
 pub mod tests;
 
-// This is a static to satisfy lifetime expectations
-lazy_static::lazy_static! {
-  static ref NEW_TRIBUTARIES: Arc<RwLock<VecDeque<TributarySpec>>> = Arc::new(
-    RwLock::new(VecDeque::new())
+struct ActiveTributary<D: Db, P: P2p> {
+  spec: TributarySpec,
+  tributary: Tributary<D, Transaction, P>,
+  // Each tributary's coordinator-side bookkeeping (scan cursor, etc.) lives in its own keyspace,
+  // partitioned by genesis, rather than sharing one flat scan cursor/cache with every other
+  // tributary, and that partition can be dropped in one pass instead of scanned key-by-key. The
+  // consensus state `Tributary` itself manages is written to the shared `Db` it's given, outside
+  // this keyspace
+  tributary_db: TributaryDb<D>,
+}
+
+async fn add_tributary<D: Db, P: P2p>(
+  db: D,
+  key: Zeroizing<<Ristretto as Ciphersuite>::F>,
+  p2p: P,
+  tributaries: &mut HashMap<[u8; 32], ActiveTributary<D, P>>,
+  spec: TributarySpec,
+) {
+  let genesis = spec.genesis();
+
+  let tributary = Tributary::<_, Transaction, _>::new(
+    db.clone(),
+    genesis,
+    spec.start_time(),
+    key,
+    spec.validators(),
+    p2p,
+  )
+  .await
+  .unwrap();
+
+  // Write-through so a crash immediately after a block is scanned can't lose the cursor, as an
+  // overwrite-on-commit policy only persists writes once the keyspace is committed. This is the
+  // coordinator's own per-tributary bookkeeping (scan cursor, etc.) rather than the consensus
+  // state `Tributary` itself manages, which is why it's scoped via `TributaryDb` instead of
+  // threading a keyspace selector through `Tributary::new` above
+  let tributary_db = TributaryDb::new_in_keyspace(
+    db,
+    genesis,
+    CacheUpdatePolicy::WriteThrough,
   );
+
+  tributaries.insert(tributary.genesis(), ActiveTributary { spec, tributary, tributary_db });
+}
+
+// Drops a retired tributary's entire keyspace in one pass, rather than scanning the shared store
+// for its keys, and removes it from the active set and from the metrics it's been accumulating
+// under, so neither grows without bound over a coordinator's lifetime as tributaries rotate
+async fn retire_tributary<D: Db, P: P2p>(
+  tributaries: &mut HashMap<[u8; 32], ActiveTributary<D, P>>,
+  metrics: &Metrics,
+  genesis: [u8; 32],
+) {
+  if let Some(ActiveTributary { tributary_db, .. }) = tributaries.remove(&genesis) {
+    tributary_db.prune_tributary(genesis);
+    metrics.remove_tributary(genesis).await;
+  }
+}
+
+// How long p2p.receive() can go without yielding a message before the transport is assumed to
+// have silently dropped its peers and is rebuilt via P2p::reconnect
+const P2P_STALL_THRESHOLD: Duration = Duration::from_secs(120);
+
+// Waits for a Ctrl-C and broadcasts a single shutdown signal to every listener
+async fn shutdown_signal() -> tokio::sync::broadcast::Sender<()> {
+  let (shutdown_send, _) = tokio::sync::broadcast::channel(1);
+  {
+    let shutdown_send = shutdown_send.clone();
+    tokio::spawn(async move {
+      let _ = tokio::signal::ctrl_c().await;
+      log::info!("received Ctrl-C, shutting down once in-flight work completes");
+      let _ = shutdown_send.send(());
+    });
+  }
+  shutdown_send
 }
 
 async fn run<D: Db, Pro: Processor, P: P2p>(
@@ -49,161 +124,169 @@ async fn run<D: Db, Pro: Processor, P: P2p>(
   key: Zeroizing<<Ristretto as Ciphersuite>::F>,
   p2p: P,
   mut processor: Pro,
-  serai: Serai,
+  serai: SeraiConnection,
+  metrics: Arc<Metrics>,
 ) {
-  let add_new_tributary = |db, spec: TributarySpec| async {
-    // Save it to the database
-    MainDb(db).add_active_tributary(&spec);
-    // Add it to the queue
-    // If we reboot before this is read from the queue, the fact it was saved to the database
-    // means it'll be handled on reboot
-    NEW_TRIBUTARIES.write().await.push_back(spec);
+  // Channel new tributaries are sent down as they're registered, so this loop can wake up and add
+  // them immediately instead of waiting on the next scan tick
+  let (new_tributary_send, mut new_tributary_recv) = mpsc::unbounded_channel::<TributarySpec>();
+
+  let add_new_tributary = {
+    let new_tributary_send = new_tributary_send.clone();
+    move |db, spec: TributarySpec| {
+      let new_tributary_send = new_tributary_send.clone();
+      async move {
+        // Save it to the database
+        MainDb(db).add_active_tributary(&spec);
+        // Send it down the channel
+        // If we reboot before this is received, the fact it was saved to the database means it'll
+        // be handled on reboot
+        new_tributary_send.send(spec).expect("new_tributary_recv was dropped");
+      }
+    }
   };
 
-  // Handle new Substrate blocks
-  {
-    let mut substrate_db = substrate::SubstrateDb::new(raw_db.clone());
-    let mut last_substrate_block = substrate_db.last_block();
+  // Paired with add_new_tributary's channel so a retirement noticed while handling a substrate
+  // block can be applied here immediately, rather than requiring retire_tributary to be called
+  // inline from within handle_new_blocks where it'd need its own mutable borrow of `tributaries`
+  let (retire_tributary_send, mut retire_tributary_recv) = mpsc::unbounded_channel::<[u8; 32]>();
 
-    let key = key.clone();
-    let mut processor = processor.clone();
-    tokio::spawn(async move {
-      loop {
-        match substrate::handle_new_blocks(
+  let on_tributary_retired = move |genesis: [u8; 32]| {
+    let retire_tributary_send = retire_tributary_send.clone();
+    async move {
+      retire_tributary_send.send(genesis).expect("retire_tributary_recv was dropped");
+    }
+  };
+
+  let mut substrate_db = substrate::SubstrateDb::new(raw_db.clone());
+  let mut last_substrate_block = substrate_db.last_block();
+
+  let mut tributaries = HashMap::<[u8; 32], ActiveTributary<D, P>>::new();
+  // Reload active tributaries from the database
+  for spec in MainDb(raw_db.clone()).active_tributaries().1 {
+    add_tributary(raw_db.clone(), key.clone(), p2p.clone(), &mut tributaries, spec).await;
+  }
+
+  let shutdown_send = shutdown_signal().await;
+  let mut shutdown_recv = shutdown_send.subscribe();
+
+  let mut substrate_scan = tokio::time::interval(Duration::from_secs(3));
+  let mut tributary_scan = tokio::time::interval(Duration::from_secs(3));
+
+  // Checked on its own tick, rather than via a `tokio::time::timeout` wrapped directly around
+  // `p2p.receive()`, since a `timeout()` future dropped by `select!` would cancel and discard
+  // whatever `receive()` was already in the middle of on the next poll. Tracking the last
+  // successful receive and comparing it against a periodic tick instead lets that in-flight
+  // receive keep running across iterations.
+  let mut p2p_stall_check = tokio::time::interval(Duration::from_secs(30));
+  let mut last_p2p_message = Instant::now();
+
+  // A single select! multiplexes every event source this coordinator reacts to, instead of
+  // detaching them into independent tasks with no shared shutdown path. `biased` puts the
+  // shutdown check first so a SIGINT is observed promptly instead of after whichever branch
+  // tokio happened to poll first, while still letting the branch already in flight finish.
+  loop {
+    tokio::select! {
+      biased;
+
+      _ = shutdown_recv.recv() => {
+        log::info!("coordinator shutting down");
+        break;
+      }
+
+      _ = substrate_scan.tick() => {
+        let start = Instant::now();
+        let result = substrate::handle_new_blocks(
           &mut substrate_db,
           &key,
-          add_new_tributary,
+          add_new_tributary.clone(),
+          on_tributary_retired.clone(),
           &mut processor,
-          &serai,
+          // Re-borrowed on every tick so a reconnect performed by the health check is picked up
+          // without this loop holding a stale handle
+          &*serai.read().await,
           &mut last_substrate_block,
         )
-        .await
-        {
-          Ok(()) => sleep(Duration::from_secs(3)).await,
-          Err(e) => {
-            log::error!("couldn't communicate with serai node: {e}");
-            sleep(Duration::from_secs(5)).await;
-          }
+        .await;
+        metrics.observe_substrate_block_handling(start.elapsed());
+        if let Err(e) = result {
+          log::error!("couldn't communicate with serai node: {e}");
         }
       }
-    });
-  }
 
-  // Handle the Tributaries
-  {
-    struct ActiveTributary<D: Db, P: P2p> {
-      spec: TributarySpec,
-      tributary: Tributary<D, Transaction, P>,
-    }
-    let tributaries = Arc::new(RwLock::new(HashMap::<[u8; 32], ActiveTributary<D, P>>::new()));
-
-    async fn add_tributary<D: Db, P: P2p>(
-      db: D,
-      key: Zeroizing<<Ristretto as Ciphersuite>::F>,
-      p2p: P,
-      tributaries: &mut HashMap<[u8; 32], ActiveTributary<D, P>>,
-      spec: TributarySpec,
-    ) {
-      let tributary = Tributary::<_, Transaction, _>::new(
-        // TODO: Use a db on a distinct volume
-        db,
-        spec.genesis(),
-        spec.start_time(),
-        key,
-        spec.validators(),
-        p2p,
-      )
-      .await
-      .unwrap();
-
-      tributaries.insert(tributary.genesis(), ActiveTributary { spec, tributary });
-    }
-
-    // Reload active tributaries from the database
-    // TODO: Can MainDb take a borrow?
-    for spec in MainDb(raw_db.clone()).active_tributaries().1 {
-      add_tributary(
-        raw_db.clone(),
-        key.clone(),
-        p2p.clone(),
-        &mut *tributaries.write().await,
-        spec,
-      )
-      .await;
-    }
+      spec = new_tributary_recv.recv() => {
+        let spec = spec.expect("new_tributary_send was dropped");
+        add_tributary(raw_db.clone(), key.clone(), p2p.clone(), &mut tributaries, spec).await;
+        metrics.tributary_added();
+      }
 
-    // Handle new Tributary blocks
-    let mut tributary_db = tributary::TributaryDb::new(raw_db.clone());
-    {
-      let tributaries = tributaries.clone();
-      let p2p = p2p.clone();
-      tokio::spawn(async move {
-        loop {
-          // The following handle_new_blocks function may take an arbitrary amount of time
-          // If registering a new tributary waited for a lock on the tributaries table, the
-          // substrate scanner may wait on a lock for an arbitrary amount of time
-          // By instead using the distinct NEW_TRIBUTARIES, there should be minimal
-          // competition/blocking
-          {
-            let mut new_tributaries = NEW_TRIBUTARIES.write().await;
-            while let Some(spec) = new_tributaries.pop_front() {
-              add_tributary(
-                raw_db.clone(),
-                key.clone(),
-                p2p.clone(),
-                // This is a short-lived write acquisition, which is why it should be fine
-                &mut *tributaries.write().await,
-                spec,
-              )
-              .await;
-            }
-          }
+      genesis = retire_tributary_recv.recv() => {
+        let genesis = genesis.expect("retire_tributary_send was dropped");
+        retire_tributary(&mut tributaries, &metrics, genesis).await;
+      }
 
-          // Unknown-length read acquisition. This would risk screwing over the P2P process EXCEPT
-          // they both use read locks. Accordingly, they can co-exist
-          for ActiveTributary { spec, tributary } in tributaries.read().await.values() {
-            tributary::scanner::handle_new_blocks::<_, _, P>(
-              &mut tributary_db,
-              &key,
-              &mut processor,
-              spec,
-              tributary,
-            )
-            .await;
-          }
+      // A periodic scan is kept, in addition to the wake-on-receive above, so a Tributary which
+      // somehow gets skipped over isn't starved of handling forever
+      _ = tributary_scan.tick() => {
+        for ActiveTributary { spec, tributary, tributary_db } in tributaries.values_mut() {
+          let start = Instant::now();
+          tributary::scanner::handle_new_blocks::<_, _, P>(
+            tributary_db,
+            &key,
+            &mut processor,
+            spec,
+            tributary,
+          )
+          .await;
+          metrics.observe_tributary_block_handling(spec.genesis(), start.elapsed()).await;
+        }
+      }
 
-          sleep(Duration::from_secs(3)).await;
+      _ = p2p_stall_check.tick() => {
+        if last_p2p_message.elapsed() >= P2P_STALL_THRESHOLD {
+          log::warn!(
+            "p2p transport has been silent for over {}s, reconnecting",
+            P2P_STALL_THRESHOLD.as_secs(),
+          );
+          p2p.reconnect().await;
+          // Avoid immediately re-triggering a reconnect on the next tick while the freshly
+          // rebuilt transport is still waiting on its first message
+          last_p2p_message = Instant::now();
         }
-      });
-    }
+      }
 
-    // Handle P2P messages
-    {
-      tokio::spawn(async move {
-        loop {
-          let msg = p2p.receive().await;
-          match msg.kind {
-            P2pMessageKind::Tributary(genesis) => {
-              let tributaries_read = tributaries.read().await;
-              let Some(tributary) = tributaries_read.get(&genesis) else {
-                log::debug!("received p2p message for unknown network");
-                continue;
-              };
-
-              if tributary.tributary.handle_message(&msg.msg).await {
-                P2p::broadcast(&p2p, msg.kind, msg.msg).await;
-              }
+      msg = p2p.receive() => {
+        let start = Instant::now();
+        last_p2p_message = Instant::now();
+        match msg.kind {
+          P2pMessageKind::Tributary(genesis) => {
+            let Some(tributary) = tributaries.get(&genesis) else {
+              log::debug!("received p2p message for unknown network");
+              continue;
+            };
+
+            if tributary.tributary.handle_message(&msg.msg).await {
+              P2p::broadcast(&p2p, msg.kind, msg.msg).await;
+              metrics.message_broadcast();
             }
           }
         }
-      });
+        metrics.observe_p2p_message_handling(start.elapsed());
+      }
+
+      msg = processor.recv() => {
+        // TODO: Dispatch on the processor message's network/kind once the processor message
+        // schema is defined; for now, this only gives the branch somewhere to live instead of
+        // remaining an unconditional todo!()
+        log::debug!("received message from processor: {msg:?}");
+      }
     }
   }
 
-  loop {
-    // Handle all messages from processors
-    todo!()
-  }
+  // Every write above went through Db's transaction API, which commits as it goes, so there's no
+  // separate flush step beyond letting the Dbs drop here
+  drop(substrate_db);
+  drop(tributaries);
 }
 
 #[tokio::main]
@@ -215,15 +298,14 @@ async fn main() {
 
   let processor = processor::MemProcessor::new(); // TODO
 
-  let serai = || async {
-    loop {
-      let Ok(serai) = Serai::new("ws://127.0.0.1:9944").await else {
-        log::error!("couldn't connect to the Serai node");
-        sleep(Duration::from_secs(5)).await;
-        continue
-      };
-      return serai;
+  let serai = SeraiConnection::new("ws://127.0.0.1:9944".to_string()).await;
+
+  let metrics = Arc::new(Metrics::new());
+  if let Ok(bind) = std::env::var("COORDINATOR_METRICS_BIND") {
+    if let Ok(bind) = bind.parse() {
+      tokio::spawn(metrics::serve(metrics.clone(), bind));
     }
-  };
-  run(db, key, p2p, processor, serai().await).await
+  }
+
+  run(db, key, p2p, processor, serai, metrics).await
 }
@@ -0,0 +1,211 @@
+use std::{
+  sync::atomic::{AtomicU64, Ordering},
+  collections::HashMap,
+};
+
+use tokio::sync::RwLock;
+
+// Fixed exponential buckets, in milliseconds: 1, 2, 4, ..., 2^(BUCKET_COUNT - 1)
+// Powers of two keep the bucket boundaries cheap to compute and wide enough to cover everything
+// from a sub-millisecond no-op scan to a multi-second stall worth alerting on
+const BUCKET_COUNT: usize = 16;
+
+fn bucket_bounds_ms() -> [u64; BUCKET_COUNT] {
+  let mut bounds = [0; BUCKET_COUNT];
+  for (i, bound) in bounds.iter_mut().enumerate() {
+    *bound = 1 << i;
+  }
+  bounds
+}
+
+// A histogram of latencies, bucketed by fixed power-of-two millisecond boundaries plus a +Inf
+// overflow bucket. Each observation is a few relaxed atomic adds, so this is cheap enough to call
+// on every loop iteration without holding any handler's lock any longer than the handler itself
+struct Histogram {
+  buckets: [AtomicU64; BUCKET_COUNT],
+  overflow: AtomicU64,
+  sum_ms: AtomicU64,
+  count: AtomicU64,
+}
+
+impl Histogram {
+  fn new() -> Histogram {
+    Histogram {
+      buckets: core::array::from_fn(|_| AtomicU64::new(0)),
+      overflow: AtomicU64::new(0),
+      sum_ms: AtomicU64::new(0),
+      count: AtomicU64::new(0),
+    }
+  }
+
+  fn observe(&self, duration: core::time::Duration) {
+    let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+
+    match bucket_bounds_ms().iter().position(|bound| ms <= *bound) {
+      Some(i) => { self.buckets[i].fetch_add(1, Ordering::Relaxed); }
+      None => { self.overflow.fetch_add(1, Ordering::Relaxed); }
+    }
+
+    self.sum_ms.fetch_add(ms, Ordering::Relaxed);
+    self.count.fetch_add(1, Ordering::Relaxed);
+  }
+
+  // Renders this histogram as Prometheus text exposition format lines for the given metric name
+  // and labels (already formatted as `{key="value",...}`, or empty)
+  fn render(&self, name: &str, labels: &str, out: &mut String) {
+    let bounds = bucket_bounds_ms();
+    let mut cumulative = 0;
+    for (bound, bucket) in bounds.iter().zip(self.buckets.iter()) {
+      cumulative += bucket.load(Ordering::Relaxed);
+      let le = if labels.is_empty() {
+        format!("{{le=\"{bound}\"}}")
+      } else {
+        format!("{}", &labels[.. labels.len() - 1]) + &format!(",le=\"{bound}\"}}")
+      };
+      out.push_str(&format!("{name}_bucket{le} {cumulative}\n"));
+    }
+    cumulative += self.overflow.load(Ordering::Relaxed);
+    let le_inf = if labels.is_empty() {
+      "{le=\"+Inf\"}".to_string()
+    } else {
+      format!("{}", &labels[.. labels.len() - 1]) + ",le=\"+Inf\"}"
+    };
+    out.push_str(&format!("{name}_bucket{le_inf} {cumulative}\n"));
+    out.push_str(&format!(
+      "{name}_sum{labels} {}\n",
+      self.sum_ms.load(Ordering::Relaxed)
+    ));
+    out.push_str(&format!(
+      "{name}_count{labels} {}\n",
+      self.count.load(Ordering::Relaxed)
+    ));
+  }
+}
+
+/// Latency histograms and counters for the coordinator's hot paths, exposed over HTTP in
+/// Prometheus text format.
+///
+/// This exists so operators can watch per-tributary scan latency and notice a tributary whose
+/// block handling is falling behind the scan interval before it results in a visible stall.
+pub struct Metrics {
+  substrate_block_handling: Histogram,
+  tributary_block_handling: RwLock<HashMap<[u8; 32], Histogram>>,
+  p2p_message_handling: Histogram,
+  tributaries_added: AtomicU64,
+  messages_broadcast: AtomicU64,
+}
+
+impl Metrics {
+  pub fn new() -> Metrics {
+    Metrics {
+      substrate_block_handling: Histogram::new(),
+      tributary_block_handling: RwLock::new(HashMap::new()),
+      p2p_message_handling: Histogram::new(),
+      tributaries_added: AtomicU64::new(0),
+      messages_broadcast: AtomicU64::new(0),
+    }
+  }
+
+  pub fn observe_substrate_block_handling(&self, duration: core::time::Duration) {
+    self.substrate_block_handling.observe(duration);
+  }
+
+  pub async fn observe_tributary_block_handling(
+    &self,
+    genesis: [u8; 32],
+    duration: core::time::Duration,
+  ) {
+    // A read lock suffices for the overwhelmingly common case of an already-observed tributary;
+    // the write-locked insert only happens once per tributary's lifetime
+    if let Some(histogram) = self.tributary_block_handling.read().await.get(&genesis) {
+      histogram.observe(duration);
+      return;
+    }
+    self
+      .tributary_block_handling
+      .write()
+      .await
+      .entry(genesis)
+      .or_insert_with(Histogram::new)
+      .observe(duration);
+  }
+
+  pub fn observe_p2p_message_handling(&self, duration: core::time::Duration) {
+    self.p2p_message_handling.observe(duration);
+  }
+
+  pub fn tributary_added(&self) {
+    self.tributaries_added.fetch_add(1, Ordering::Relaxed);
+  }
+
+  /// Drops a retired tributary's histogram, so a long-running coordinator that rotates many
+  /// tributaries doesn't accumulate one stale entry per retired genesis forever.
+  pub async fn remove_tributary(&self, genesis: [u8; 32]) {
+    self.tributary_block_handling.write().await.remove(&genesis);
+  }
+
+  pub fn message_broadcast(&self) {
+    self.messages_broadcast.fetch_add(1, Ordering::Relaxed);
+  }
+
+  async fn render(&self) -> String {
+    let mut out = String::new();
+
+    self.substrate_block_handling.render(
+      "coordinator_substrate_block_handling_ms",
+      "",
+      &mut out,
+    );
+
+    for (genesis, histogram) in self.tributary_block_handling.read().await.iter() {
+      histogram.render(
+        "coordinator_tributary_block_handling_ms",
+        &format!("{{genesis=\"{}\"}}", hex::encode(genesis)),
+        &mut out,
+      );
+    }
+
+    self.p2p_message_handling.render("coordinator_p2p_message_handling_ms", "", &mut out);
+
+    out.push_str(&format!(
+      "coordinator_tributaries_added_total {}\n",
+      self.tributaries_added.load(Ordering::Relaxed)
+    ));
+    out.push_str(&format!(
+      "coordinator_messages_broadcast_total {}\n",
+      self.messages_broadcast.load(Ordering::Relaxed)
+    ));
+
+    out
+  }
+}
+
+/// Serves the current metrics in Prometheus text format at `/metrics` on the given bind address.
+/// This is intentionally optional: a deployment which doesn't scrape Prometheus shouldn't have to
+/// run a listener it'll never use.
+pub async fn serve(metrics: std::sync::Arc<Metrics>, bind: std::net::SocketAddr) {
+  use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpListener};
+
+  let Ok(listener) = TcpListener::bind(bind).await else {
+    log::error!("couldn't bind the metrics HTTP listener on {bind}");
+    return;
+  };
+
+  loop {
+    let Ok((mut stream, _)) = listener.accept().await else { continue };
+    let metrics = metrics.clone();
+    tokio::spawn(async move {
+      // Just enough of a request line to discard before replying; this is a scrape-only endpoint
+      let mut buf = [0; 1024];
+      let _ = stream.read(&mut buf).await;
+
+      let body = metrics.render().await;
+      let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body,
+      );
+      let _ = stream.write_all(response.as_bytes()).await;
+    });
+  }
+}
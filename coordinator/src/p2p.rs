@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Mutex};
+
+/// Identifies which consumer inside the coordinator a P2P message is destined for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum P2pMessageKind {
+  Tributary([u8; 32]),
+}
+
+/// A message received from a peer, tagged with where it should be routed.
+#[derive(Clone, Debug)]
+pub struct P2pMessage {
+  pub kind: P2pMessageKind,
+  pub msg: Vec<u8>,
+}
+
+/// The P2P transport a `Tributary` broadcasts over and receives consensus messages from.
+///
+/// `reconnect` is a required method, not a default no-op, so that `run`'s stall-detection branch
+/// (triggered once `receive` has gone quiet for longer than expected) has an actual rebuild path
+/// to call into for every transport, mirroring how `SeraiConnection` rebuilds its RPC handle on a
+/// failed health check rather than merely logging the staleness.
+///
+/// `receive` MUST be cancel-safe: it's polled as one branch of a `select!` alongside several
+/// others (substrate/tributary scan ticks, the stall check, shutdown), any of which can become
+/// ready first and cause an in-flight `receive` call to be dropped before it resolves. An
+/// implementation that assembles a message across multiple `.await` points (e.g. reading a framed
+/// message off a raw stream) must buffer that partial progress somewhere that survives being
+/// dropped mid-poll — typically by having a dedicated task own the socket and funnel complete
+/// messages through an mpsc channel, so `receive` is just `.recv()` on that channel, which tokio
+/// guarantees not to lose a message it's already taken off the channel even if the `recv()` call
+/// itself is cancelled.
+pub trait P2p: Clone + Send + Sync + 'static {
+  /// Waits for the next message from any peer.
+  fn receive(&self) -> impl core::future::Future<Output = P2pMessage> + Send;
+  /// Sends a message to every other peer.
+  fn broadcast(&self, kind: P2pMessageKind, msg: Vec<u8>) -> impl core::future::Future<Output = ()> + Send;
+  /// Rebuilds this transport's underlying connections. Called once `receive` has been silent for
+  /// longer than `P2P_STALL_THRESHOLD`, on the assumption a transport which hasn't delivered
+  /// anything in that long has silently dropped its peers rather than the network simply being
+  /// quiet.
+  fn reconnect(&self) -> impl core::future::Future<Output = ()> + Send;
+}
+
+// An in-memory P2p used by tests and by `main` until a real transport is wired in. Every node
+// holds the receiving end of its own mpsc channel, and `broadcast` sends directly into every
+// other node's sender half, so `receive` is a plain `.recv()` — cancel-safe per the `P2p::receive`
+// contract above without needing a dedicated forwarding task, since there's no raw socket here for
+// one to assemble a message off of in the first place.
+#[derive(Clone)]
+pub struct LocalP2p {
+  i: usize,
+  senders: Arc<Vec<mpsc::UnboundedSender<P2pMessage>>>,
+  receiver: Arc<Mutex<mpsc::UnboundedReceiver<P2pMessage>>>,
+}
+
+impl LocalP2p {
+  pub fn new(nodes: usize) -> Vec<LocalP2p> {
+    let mut senders = Vec::with_capacity(nodes);
+    let mut receivers = Vec::with_capacity(nodes);
+    for _ in 0 .. nodes {
+      let (send, recv) = mpsc::unbounded_channel();
+      senders.push(send);
+      receivers.push(recv);
+    }
+    let senders = Arc::new(senders);
+
+    receivers
+      .into_iter()
+      .enumerate()
+      .map(|(i, recv)| LocalP2p { i, senders: senders.clone(), receiver: Arc::new(Mutex::new(recv)) })
+      .collect()
+  }
+}
+
+impl P2p for LocalP2p {
+  async fn receive(&self) -> P2pMessage {
+    self.receiver.lock().await.recv().await.expect("every LocalP2p sender was dropped")
+  }
+
+  async fn broadcast(&self, kind: P2pMessageKind, msg: Vec<u8>) {
+    for (i, sender) in self.senders.iter().enumerate() {
+      if i != self.i {
+        // Only fails if that peer's LocalP2p (and its receiver) has been dropped entirely, which
+        // isn't a broadcast-time error so much as that peer no longer existing
+        let _ = sender.send(P2pMessage { kind, msg: msg.clone() });
+      }
+    }
+  }
+
+  // There's no actual socket backing this transport, just a shared in-memory channel, so there's
+  // nothing to rebuild; this exists solely so `LocalP2p` satisfies the trait the same way a real
+  // transport (which does have a reconnect path) would
+  async fn reconnect(&self) {}
+}
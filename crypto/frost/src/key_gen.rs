@@ -6,8 +6,142 @@ use rand_core::{RngCore, CryptoRng};
 use ff::{Field, PrimeField};
 use group::Group;
 
+use chacha20poly1305::{aead::{Aead, Payload, KeyInit}, ChaCha20Poly1305, Key};
+use x25519_dalek::{StaticSecret, PublicKey as CommKey};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
 use crate::{Curve, MultisigParams, MultisigKeys, FrostError, validate_map};
 
+const COMM_KEY_LEN: usize = 32;
+
+// C::F is a generic PrimeField and can't itself implement Zeroize, so clearing a scalar is done
+// by this helper, generic over the curve, which overwrites it with the field's zero
+fn clear_f<C: Curve>(f: &mut C::F) {
+  *f = C::F::zero();
+}
+
+/// A polynomial's secret coefficients. Overwritten with the field's zero when dropped, so neither
+/// a state transition nor the end of the function that generated them leaves them sitting in
+/// memory afterwards
+struct Coefficients<C: Curve>(Vec<C::F>);
+
+impl<C: Curve> Coefficients<C> {
+  fn as_slice(&self) -> &[C::F] {
+    &self.0
+  }
+
+  // `Coefficients` implements `Drop` (to zeroize on scope exit), so `self.0` can't be moved out
+  // of a by-value `self` directly (rustc E0509); take it via `mem::take` instead, leaving an
+  // empty, already-zero Vec behind for `Drop` to run over harmlessly
+  fn into_inner(mut self) -> Vec<C::F> {
+    core::mem::take(&mut self.0)
+  }
+}
+
+impl<C: Curve> Zeroize for Coefficients<C> {
+  fn zeroize(&mut self) {
+    for f in self.0.iter_mut() {
+      clear_f::<C>(f);
+    }
+  }
+}
+
+impl<C: Curve> Drop for Coefficients<C> {
+  fn drop(&mut self) {
+    self.zeroize();
+  }
+}
+
+impl<C: Curve> ZeroizeOnDrop for Coefficients<C> {}
+
+/// A single secret scalar, such as this participant's locally computed secret share, overwritten
+/// with the field's zero when dropped.
+struct SecretScalar<C: Curve>(C::F);
+
+impl<C: Curve> Zeroize for SecretScalar<C> {
+  fn zeroize(&mut self) {
+    clear_f::<C>(&mut self.0);
+  }
+}
+
+impl<C: Curve> Drop for SecretScalar<C> {
+  fn drop(&mut self) {
+    self.zeroize();
+  }
+}
+
+impl<C: Curve> ZeroizeOnDrop for SecretScalar<C> {}
+
+// Derives an AEAD over the ECDH shared secret between a share's sender and recipient, binding the
+// associated data to both indexes and the DKG context so a ciphertext can't be replayed between
+// parties or sessions. The raw X25519 output is run through HKDF-SHA256, with the DKG context as
+// the info parameter, rather than used as the cipher key directly: a raw DH output isn't
+// guaranteed to be uniform over the key's bit space the way a KDF's output is. The info also
+// binds the ordered (sender, recipient) pair, as the DH output itself is the same in both
+// directions
+fn share_cipher(
+  secret: &StaticSecret,
+  other: &CommKey,
+  sender: u16,
+  recipient: u16,
+  context: &str,
+) -> ChaCha20Poly1305 {
+  let shared_secret = secret.diffie_hellman(other);
+  let mut key = [0; 32];
+  Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+    .expand(&share_aad(sender, recipient, context), &mut key)
+    .expect("32 bytes is a valid HKDF-SHA256 output length");
+  ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn share_aad(sender: u16, recipient: u16, context: &str) -> Vec<u8> {
+  let mut aad = Vec::with_capacity(4 + context.len());
+  aad.extend(sender.to_be_bytes());
+  aad.extend(recipient.to_be_bytes());
+  aad.extend(context.as_bytes());
+  aad
+}
+
+// Encrypts a dealt secret share for a specific recipient. The sender's static communication
+// secret is combined via ECDH with the recipient's published communication key from round 1 to
+// derive a per-(sender, recipient, session) AEAD key, so a nonce of all zeroes is safe: this key
+// is used to encrypt exactly one message, ever
+fn encrypt_share<C: Curve>(
+  sender: u16,
+  recipient: u16,
+  context: &str,
+  our_comm_secret: &StaticSecret,
+  recipient_comm_key: &CommKey,
+  share: C::F,
+) -> Vec<u8> {
+  let cipher = share_cipher(our_comm_secret, recipient_comm_key, sender, recipient, context);
+  let aad = share_aad(sender, recipient, context);
+  cipher
+    .encrypt(&Default::default(), Payload { msg: &C::F_to_bytes(&share), aad: &aad })
+    .expect("encryption with a freshly derived AEAD key cannot fail")
+}
+
+// Decrypts and authenticates a dealt secret share. A failure is solely attributable to `sender`:
+// either they encrypted under the wrong key/associated data, or they (or a relay) corrupted the
+// ciphertext in transit
+fn decrypt_share<C: Curve>(
+  sender: u16,
+  recipient: u16,
+  context: &str,
+  our_comm_secret: &StaticSecret,
+  sender_comm_key: &CommKey,
+  ciphertext: &[u8],
+) -> Result<C::F, FrostError> {
+  let cipher = share_cipher(our_comm_secret, sender_comm_key, sender, recipient, context);
+  let aad = share_aad(sender, recipient, context);
+  let plaintext = cipher
+    .decrypt(&Default::default(), Payload { msg: ciphertext, aad: &aad })
+    .map_err(|_| FrostError::InvalidShare(sender))?;
+  C::F_from_slice(&plaintext).map_err(|_| FrostError::InvalidShare(sender))
+}
+
 #[allow(non_snake_case)]
 fn challenge<C: Curve>(l: u16, context: &str, R: &[u8], Am: &[u8]) -> C::F {
   let mut c = Vec::with_capacity(2 + context.len() + R.len() + Am.len());
@@ -20,17 +154,19 @@ fn challenge<C: Curve>(l: u16, context: &str, R: &[u8], Am: &[u8]) -> C::F {
   C::hash_to_F(&c)
 }
 
-// Implements steps 1 through 3 of round 1 of FROST DKG. Returns the coefficients, commitments, and
-// the serialized commitments to be broadcasted over an authenticated channel to all parties
+// Implements steps 1 through 3 of round 1 of FROST DKG. Returns the coefficients, this
+// participant's communication secret (used to encrypt/decrypt secret shares in round 2), and the
+// serialized commitments to be broadcasted over an authenticated channel to all parties
 fn generate_key_r1<R: RngCore + CryptoRng, C: Curve>(
   rng: &mut R,
   params: &MultisigParams,
   context: &str,
-) -> (Vec<C::F>, Vec<u8>) {
+) -> (Vec<C::F>, StaticSecret, Vec<u8>) {
   let t = usize::from(params.t);
   let mut coefficients = Vec::with_capacity(t);
   let mut commitments = Vec::with_capacity(t);
-  let mut serialized = Vec::with_capacity((C::G_len() * t) + C::G_len() + C::F_len());
+  let mut serialized =
+    Vec::with_capacity((C::G_len() * t) + C::G_len() + C::F_len() + COMM_KEY_LEN);
 
   for i in 0 .. t {
     // Step 1: Generate t random values to form a polynomial with
@@ -55,18 +191,26 @@ fn generate_key_r1<R: RngCore + CryptoRng, C: Curve>(
   serialized.extend(&C::G_to_bytes(&R));
   serialized.extend(&C::F_to_bytes(&s));
 
+  // Publish a static communication key so this participant's round 2 shares can be decrypted by
+  // their recipients, and shares dealt to this participant can be encrypted to it. Appending it
+  // here, rather than requiring a side channel, means the same authenticated broadcast already
+  // used for the commitments is sufficient to distribute it
+  let comm_secret = StaticSecret::random_from_rng(&mut *rng);
+  serialized.extend(CommKey::from(&comm_secret).as_bytes());
+
   // Step 4: Broadcast
-  (coefficients, serialized)
+  (coefficients, comm_secret, serialized)
 }
 
-// Verify the received data from the first round of key generation
+// Verify the received data from the first round of key generation. Returns, alongside each
+// party's commitments, the communication key they published for encrypting round 2 shares
 fn verify_r1<R: RngCore + CryptoRng, C: Curve>(
   rng: &mut R,
   params: &MultisigParams,
   context: &str,
   our_commitments: Vec<u8>,
   mut serialized: HashMap<u16, Vec<u8>>,
-) -> Result<HashMap<u16, Vec<C::G>>, FrostError> {
+) -> Result<(HashMap<u16, Vec<C::G>>, HashMap<u16, CommKey>), FrostError> {
   validate_map(
     &mut serialized,
     &(1 ..= params.n()).into_iter().collect::<Vec<_>>(),
@@ -74,8 +218,10 @@ fn verify_r1<R: RngCore + CryptoRng, C: Curve>(
   )?;
 
   let commitments_len = usize::from(params.t()) * C::G_len();
+  let comm_key_offset = commitments_len + C::G_len() + C::F_len();
 
   let mut commitments = HashMap::new();
+  let mut comm_keys = HashMap::new();
 
   #[allow(non_snake_case)]
   let R_bytes = |l| &serialized[&l][commitments_len .. commitments_len + C::G_len()];
@@ -85,7 +231,7 @@ fn verify_r1<R: RngCore + CryptoRng, C: Curve>(
   let Am = |l| &serialized[&l][0 .. commitments_len];
 
   let s = |l| C::F_from_slice(
-    &serialized[&l][commitments_len + C::G_len() ..]
+    &serialized[&l][commitments_len + C::G_len() .. comm_key_offset]
   ).map_err(|_| FrostError::InvalidProofOfKnowledge(l));
 
   let mut first = true;
@@ -102,6 +248,10 @@ fn verify_r1<R: RngCore + CryptoRng, C: Curve>(
     }
     commitments.insert(l, these_commitments);
 
+    let mut comm_key = [0; COMM_KEY_LEN];
+    comm_key.copy_from_slice(&serialized[&l][comm_key_offset .. comm_key_offset + COMM_KEY_LEN]);
+    comm_keys.insert(l, CommKey::from(comm_key));
+
     // Don't bother validating our own proof of knowledge
     if l == params.i() {
       continue;
@@ -149,7 +299,7 @@ fn verify_r1<R: RngCore + CryptoRng, C: Curve>(
     Err(FrostError::InternalError("batch validation is broken".to_string()))?;
   }
 
-  Ok(commitments)
+  Ok((commitments, comm_keys))
 }
 
 fn polynomial<F: PrimeField>(
@@ -168,19 +318,25 @@ fn polynomial<F: PrimeField>(
 }
 
 // Implements round 1, step 5 and round 2, step 1 of FROST key generation
-// Returns our secret share part, commitments for the next step, and a vector for each
-// counterparty to receive
+// Returns our secret share part, the other parties' commitments and communication keys, and an
+// authenticated-encrypted share for each counterparty to receive
 fn generate_key_r2<R: RngCore + CryptoRng, C: Curve>(
   rng: &mut R,
   params: &MultisigParams,
   context: &str,
   coefficients: Vec<C::F>,
+  comm_secret: StaticSecret,
   our_commitments: Vec<u8>,
   commitments: HashMap<u16, Vec<u8>>,
-) -> Result<(C::F, HashMap<u16, Vec<C::G>>, HashMap<u16, Vec<u8>>), FrostError> {
-  let commitments = verify_r1::<R, C>(rng, params, context, our_commitments, commitments)?;
+) -> Result<(SecretScalar<C>, HashMap<u16, Vec<C::G>>, HashMap<u16, CommKey>, HashMap<u16, Vec<u8>>), FrostError> {
+  let (commitments, comm_keys) = verify_r1::<R, C>(rng, params, context, our_commitments, commitments)?;
+
+  // Wrapped so the coefficients are overwritten with the field's zero once this function returns,
+  // rather than left sitting in whatever memory the caller's Vec happened to occupy
+  let coefficients = Coefficients::<C>(coefficients);
 
-  // Step 1: Generate secret shares for all other parties
+  // Step 1: Generate secret shares for all other parties, encrypted so the broadcast channel
+  // carrying them need only be authenticated, not confidential
   let mut res = HashMap::new();
   for l in 1 ..= params.n() {
     // Don't insert our own shares to the byte buffer which is meant to be sent around
@@ -189,47 +345,34 @@ fn generate_key_r2<R: RngCore + CryptoRng, C: Curve>(
       continue;
     }
 
-    res.insert(l, C::F_to_bytes(&polynomial(&coefficients, l)));
+    res.insert(
+      l,
+      encrypt_share::<C>(
+        params.i(),
+        l,
+        context,
+        &comm_secret,
+        &comm_keys[&l],
+        polynomial(coefficients.as_slice(), l),
+      ),
+    );
   }
 
   // Calculate our own share
-  let share = polynomial(&coefficients, params.i());
-
-  // The secret shares are discarded here, not cleared. While any system which leaves its memory
-  // accessible is likely totally lost already, making the distinction meaningless when the key gen
-  // system acts as the signer system and therefore actively holds the signing key anyways, it
-  // should be overwritten with /dev/urandom in the name of security (which still doesn't meet
-  // requirements for secure data deletion yet those requirements expect hardware access which is
-  // far past what this library can reasonably counter)
-  // TODO: Zero out the coefficients
+  let share = SecretScalar(polynomial(coefficients.as_slice(), params.i()));
 
-  Ok((share, commitments, res))
+  Ok((share, commitments, comm_keys, res))
 }
 
-/// Finishes round 2 and returns both the secret share and the serialized public key.
-/// This key is not usable until all parties confirm they have completed the protocol without
-/// issue, yet simply confirming protocol completion without issue is enough to confirm the same
-/// key was generated as long as a lack of duplicated commitments was also confirmed when they were
-/// broadcasted initially
-fn complete_r2<C: Curve>(
+// Verifies each share against the sender's broadcast commitments and sums them into a secret
+// share, deriving the verification shares and group key. Shared between the two-round protocol
+// (once its shares have been decrypted) and any other DKG mode which arrives at the same
+// plaintext shares + commitments by a different route (e.g. SimplPedPoP's single broadcast)
+fn finalize_shares<C: Curve>(
   params: MultisigParams,
-  share: C::F,
+  mut shares: HashMap<u16, C::F>,
   commitments: HashMap<u16, Vec<C::G>>,
-  // Vec to preserve ownership
-  mut serialized: HashMap<u16, Vec<u8>>,
 ) -> Result<MultisigKeys<C>, FrostError> {
-  validate_map(
-    &mut serialized,
-    &(1 ..= params.n()).into_iter().collect::<Vec<_>>(),
-    (params.i(), C::F_to_bytes(&share))
-  )?;
-
-  // Step 2. Verify each share
-  let mut shares = HashMap::new();
-  for (l, share) in serialized {
-    shares.insert(l, C::F_from_slice(&share).map_err(|_| FrostError::InvalidShare(params.i()))?);
-  }
-
   for (l, share) in &shares {
     if *l == params.i() {
       continue;
@@ -249,11 +392,12 @@ fn complete_r2<C: Curve>(
     }
   }
 
-  // TODO: Clear the original share
-
+  // Each share is overwritten with the field's zero as soon as it's been folded into
+  // secret_share, rather than left decrypted (or, for SimplPedPoP, plaintext) in the map
   let mut secret_share = C::F::zero();
-  for (_, share) in shares {
-    secret_share += share;
+  for (_, share) in shares.iter_mut() {
+    secret_share += *share;
+    clear_f::<C>(share);
   }
 
   let mut verification_shares = HashMap::new();
@@ -276,11 +420,45 @@ fn complete_r2<C: Curve>(
 
   let group_key = commitments.iter().map(|(_, commitments)| commitments[0]).sum();
 
-  // TODO: Clear serialized and shares
-
   Ok(MultisigKeys { params, secret_share, group_key, verification_shares, offset: None } )
 }
 
+/// Finishes round 2 and returns both the secret share and the serialized public key.
+/// This key is not usable until all parties confirm they have completed the protocol without
+/// issue, yet simply confirming protocol completion without issue is enough to confirm the same
+/// key was generated as long as a lack of duplicated commitments was also confirmed when they were
+/// broadcasted initially
+fn complete_r2<C: Curve>(
+  params: MultisigParams,
+  context: &str,
+  share: C::F,
+  comm_secret: StaticSecret,
+  comm_keys: HashMap<u16, CommKey>,
+  commitments: HashMap<u16, Vec<C::G>>,
+  // Vec to preserve ownership
+  mut serialized: HashMap<u16, Vec<u8>>,
+) -> Result<MultisigKeys<C>, FrostError> {
+  validate_map(
+    &mut serialized,
+    &(1 ..= params.n()).into_iter().collect::<Vec<_>>(),
+    (params.i(), C::F_to_bytes(&share))
+  )?;
+
+  // Step 2: Decrypt and authenticate each share, attributing a failure to its specific sender,
+  // before validating it against their commitments
+  let mut shares = HashMap::new();
+  for (l, ciphertext) in serialized {
+    let share = if l == params.i() {
+      C::F_from_slice(&ciphertext).map_err(|_| FrostError::InvalidShare(params.i()))?
+    } else {
+      decrypt_share::<C>(l, params.i(), context, &comm_secret, &comm_keys[&l], &ciphertext)?
+    };
+    shares.insert(l, share);
+  }
+
+  finalize_shares(params, shares, commitments)
+}
+
 /// State of a Key Generation machine
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum State {
@@ -302,9 +480,11 @@ pub struct StateMachine<C: Curve> {
   params: MultisigParams,
   context: String,
   state: State,
-  coefficients: Option<Vec<C::F>>,
+  coefficients: Option<Coefficients<C>>,
+  comm_secret: Option<StaticSecret>,
   our_commitments: Option<Vec<u8>>,
-  secret: Option<C::F>,
+  secret: Option<SecretScalar<C>>,
+  comm_keys: Option<HashMap<u16, CommKey>>,
   commitments: Option<HashMap<u16, Vec<C::G>>>
 }
 
@@ -317,8 +497,10 @@ impl<C: Curve> StateMachine<C> {
       context,
       state: State::Fresh,
       coefficients: None,
+      comm_secret: None,
       our_commitments: None,
       secret: None,
+      comm_keys: None,
       commitments: None
     }
   }
@@ -334,13 +516,14 @@ impl<C: Curve> StateMachine<C> {
       Err(FrostError::InvalidKeyGenTransition(State::Fresh, self.state))?;
     }
 
-    let (coefficients, serialized) = generate_key_r1::<R, C>(
+    let (coefficients, comm_secret, serialized) = generate_key_r1::<R, C>(
       rng,
       &self.params,
       &self.context,
     );
 
-    self.coefficients = Some(coefficients);
+    self.coefficients = Some(Coefficients(coefficients));
+    self.comm_secret = Some(comm_secret);
     self.our_commitments = Some(serialized.clone());
     self.state = State::GeneratedCoefficients;
     Ok(serialized)
@@ -349,8 +532,9 @@ impl<C: Curve> StateMachine<C> {
   /// Continue generating a key
   /// Takes in everyone else's commitments, which are expected to be in a Vec where participant
   /// index = Vec index. An empty vector is expected at index 0 to allow for this. An empty vector
-  /// is also expected at index i which is locally handled. Returns a byte vector representing a
-  /// secret share for each other participant which should be encrypted before sending
+  /// is also expected at index i which is locally handled. Returns a byte vector representing an
+  /// authenticated-encrypted secret share for each other participant, ready to send as-is over a
+  /// merely authenticated (not confidential) channel
   pub fn generate_secret_shares<R: RngCore + CryptoRng>(
     &mut self,
     rng: &mut R,
@@ -360,16 +544,20 @@ impl<C: Curve> StateMachine<C> {
       Err(FrostError::InvalidKeyGenTransition(State::GeneratedCoefficients, self.state))?;
     }
 
-    let (secret, commitments, shares) = generate_key_r2::<R, C>(
+    let (secret, commitments, comm_keys, shares) = generate_key_r2::<R, C>(
       rng,
       &self.params,
       &self.context,
-      self.coefficients.take().unwrap(),
+      self.coefficients.take().unwrap().into_inner(),
+      // Cloned, not taken: our own communication secret is needed again in `complete` to
+      // decrypt the shares dealt to us
+      self.comm_secret.as_ref().unwrap().clone(),
       self.our_commitments.take().unwrap(),
       commitments,
     )?;
 
     self.secret = Some(secret);
+    self.comm_keys = Some(comm_keys);
     self.commitments = Some(commitments);
     self.state = State::GeneratedSecretShares;
     Ok(shares)
@@ -391,7 +579,10 @@ impl<C: Curve> StateMachine<C> {
 
     let keys = complete_r2(
       self.params,
-      self.secret.take().unwrap(),
+      &self.context,
+      self.secret.take().unwrap().0,
+      self.comm_secret.take().unwrap(),
+      self.comm_keys.take().unwrap(),
       self.commitments.take().unwrap(),
       shares,
     )?;
@@ -408,3 +599,999 @@ impl<C: Curve> StateMachine<C> {
     self.state
   }
 }
+
+/// Repairs a participant's lost secret share without requiring a full re-run of the DKG.
+///
+/// If a participant loses its `secret_share`, the only recourse with `StateMachine` alone is to
+/// regenerate the entire group key. This instead implements a Stinson-Wei style repairable secret
+/// sharing round: a helper subset `S` of exactly `t` other participants jointly reconstruct the
+/// value of the sharing polynomial at the lost participant's index, without any single helper ever
+/// holding (or transmitting) the recovered share in one piece.
+///
+/// The round has three steps, run per the protocol's transcript rather than as a single function
+/// call, since each step's output must reach a different set of parties:
+/// 1. Each helper `l` calls [`split_contribution`] to additively split its piece of the
+///    interpolation into one summand per helper, sending one summand to each other helper.
+/// 2. Each helper calls [`aggregate_received`] on the summands it received (plus its own retained
+///    piece) and sends the resulting aggregate, and only the aggregate, to the target.
+/// 3. The target calls [`reconstruct_share`] on the `t` aggregates it received to recover its
+///    share, which is checked against its own `verification_shares` entry before acceptance.
+///
+/// If step 3 fails, [`verify_helper_contribution`] lets the target (or anyone holding the
+/// disputed `zeta_l`) localize blame to the specific helper whose contribution was invalid,
+/// without requiring every helper to reveal their contribution.
+pub mod repair {
+  use std::collections::HashMap;
+
+  use rand_core::{RngCore, CryptoRng};
+  use ff::{Field, PrimeField};
+
+  use crate::{Curve, FrostError};
+
+  // Computes the Lagrange coefficient L_l = prod_{j in helpers, j != l} (at - j) / (l - j), used
+  // to evaluate the sharing polynomial at `at` from the helper set's shares
+  fn lagrange_coefficient<C: Curve>(l: u16, at: u16, helpers: &[u16]) -> C::F {
+    let at = C::F::from(u64::from(at));
+    let l_scalar = C::F::from(u64::from(l));
+
+    let mut numerator = C::F::one();
+    let mut denominator = C::F::one();
+    for &j in helpers {
+      if j == l {
+        continue;
+      }
+      let j_scalar = C::F::from(u64::from(j));
+      numerator *= at - j_scalar;
+      denominator *= l_scalar - j_scalar;
+    }
+    numerator * denominator.invert().unwrap()
+  }
+
+  /// One helper's additive split of its Lagrange-weighted contribution, with one summand destined
+  /// for each helper in the set (step 1).
+  pub struct HelperSplit<C: Curve> {
+    // Keyed by the recipient helper's participant index
+    summands: HashMap<u16, C::F>,
+  }
+
+  /// Step 1: helper `l` computes `zeta_l = L_l * secret_share_l` and splits it into one random
+  /// summand per helper in `helpers` (summing to `zeta_l`), so no single summand leaks `zeta_l`.
+  pub fn split_contribution<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    l: u16,
+    target: u16,
+    secret_share_l: C::F,
+    helpers: &[u16],
+  ) -> HelperSplit<C> {
+    let zeta_l = lagrange_coefficient::<C>(l, target, helpers) * secret_share_l;
+
+    let mut summands = HashMap::new();
+    let mut remaining = zeta_l;
+    for (idx, &helper) in helpers.iter().enumerate() {
+      // The last summand absorbs whatever remains so the set sums exactly to zeta_l
+      if idx == (helpers.len() - 1) {
+        summands.insert(helper, remaining);
+        break;
+      }
+      let summand = C::F::random(&mut *rng);
+      remaining -= summand;
+      summands.insert(helper, summand);
+    }
+
+    HelperSplit { summands }
+  }
+
+  /// Step 2: helper `this_helper` sums the summand it received from every other helper's
+  /// [`HelperSplit`] (each helper's own split includes a summand addressed to itself). The result,
+  /// and only the result, is sent on to the target.
+  pub fn aggregate_received<C: Curve>(this_helper: u16, splits: &[HelperSplit<C>]) -> C::F {
+    splits.iter().fold(C::F::zero(), |acc, split| acc + split.summands[&this_helper])
+  }
+
+  /// Step 3: the target sums the `t` aggregates it received from the helper set. Since
+  /// `sum_{l in helpers} zeta_l == f(target)`, exactly the lost share, this is the recovered share.
+  /// It's validated against the target's own `verification_shares` entry before being accepted, so
+  /// a single misbehaving helper can't silently corrupt the recovered share.
+  pub fn reconstruct_share<C: Curve>(
+    aggregates: &[C::F],
+    verification_share: C::G,
+  ) -> Result<C::F, FrostError> {
+    let recovered = aggregates.iter().fold(C::F::zero(), |acc, a| acc + a);
+    if (C::generator_table() * recovered) != verification_share {
+      Err(FrostError::InternalError(
+        "recovered share didn't match its verification share".to_string(),
+      ))?;
+    }
+    Ok(recovered)
+  }
+
+  /// Localizes blame to helper `l` if its contribution was invalid, without requiring every
+  /// helper in the set to reveal their contribution. This only needs to be called, against the
+  /// disputed helper's revealed `zeta_l`, once [`reconstruct_share`] has failed.
+  pub fn verify_helper_contribution<C: Curve>(
+    l: u16,
+    target: u16,
+    helpers: &[u16],
+    zeta_l: C::F,
+    verification_share_l: C::G,
+  ) -> Result<(), FrostError> {
+    let expected = verification_share_l * lagrange_coefficient::<C>(l, target, helpers);
+    if (C::generator_table() * zeta_l) != expected {
+      Err(FrostError::InvalidShare(l))?;
+    }
+    Ok(())
+  }
+}
+
+/// A SimplPedPoP-style DKG mode which collapses `StateMachine`'s two interactive rounds
+/// (`generate_coefficients` then `generate_secret_shares`) into a single broadcast.
+///
+/// Dealing shares doesn't actually depend on anyone else's commitments, so there's no need to
+/// wait on a first round to complete before dealing them: each participant broadcasts its
+/// coefficient commitments, its proof of possession, and its per-recipient shares all in one
+/// message. The verification math receiving parties run is identical to the two-round protocol
+/// ([`verify_r1`] for the batched proofs-of-knowledge, then [`complete_r2`] for each share against
+/// its broadcast commitments) — what's collapsed is purely the wire-level round trip, not the
+/// underlying checks.
+///
+/// Because there's no second round in which to confirm "everyone processed the same broadcasts",
+/// [`recipients_set_hash`] gives honest parties a deterministic value to compare out of band,
+/// standing in for that confirmation.
+pub mod simplpedpop {
+  use std::collections::HashMap;
+
+  use rand_core::{RngCore, CryptoRng};
+
+  use crate::{Curve, MultisigParams, MultisigKeys, FrostError};
+
+  /// Generates this participant's single broadcast contribution: its coefficient commitments
+  /// (with proof of possession) and the shares dealt from those coefficients to every other
+  /// participant. Unlike `StateMachine::generate_secret_shares`, the returned shares are
+  /// plaintext and must be encrypted before being sent to their recipient.
+  ///
+  /// Returns this participant's own share (needed locally to call [`complete`]), the serialized
+  /// commitments to broadcast, and the per-recipient shares.
+  ///
+  /// The dealt shares can't be encrypted the way `StateMachine::generate_secret_shares` encrypts
+  /// them: that scheme derives each share's AEAD key via ECDH against a recipient's communication
+  /// key published in round 1, but here there is no prior round in which to have learned it before
+  /// dealing shares in this very message. They're plaintext, same as the two-round protocol was
+  /// before it gained encryption, and must be sent over a confidential channel or encrypted by the
+  /// caller.
+  ///
+  /// This doesn't additionally sign the serialized `(commitments, shares)` pair as one message.
+  /// `generate_key_r1`'s commitments are already required to go out "over an authenticated
+  /// channel to all parties" (see its doc comment) the same as the two-round protocol's are, and
+  /// the proof of possession folded into those commitments already proves whoever sent them holds
+  /// `a_{i0}` — a transport-level signature over the bytes would be binding the same authorship
+  /// claim a second time rather than adding one. It'd only earn its keep against a channel that
+  /// isn't already authenticated, which isn't an assumption the rest of this module relies on
+  /// either.
+  pub fn generate_contribution<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    params: &MultisigParams,
+    context: &str,
+  ) -> (C::F, Vec<u8>, HashMap<u16, Vec<u8>>) {
+    let (coefficients, _comm_secret, serialized_commitments) =
+      super::generate_key_r1::<R, C>(rng, params, context);
+
+    let mut shares = HashMap::new();
+    for l in 1 ..= params.n() {
+      if l == params.i() {
+        continue;
+      }
+      shares.insert(l, C::F_to_bytes(&super::polynomial(&coefficients, l)));
+    }
+    let our_share = super::polynomial(&coefficients, params.i());
+
+    (our_share, serialized_commitments, shares)
+  }
+
+  /// A deterministic transcript over the contributions a party processed (each contributor's
+  /// index and serialized commitments), letting all honest parties confirm out of band that they
+  /// derived the group key from the identical set of contributions. This is the equivalent of the
+  /// aggregated certificate / "all parties confirm completion" step the two-round protocol
+  /// requires out of band.
+  pub fn recipients_set_hash<C: Curve>(
+    context: &str,
+    contributions: &HashMap<u16, Vec<u8>>,
+  ) -> Vec<u8> {
+    let mut sorted = contributions.keys().copied().collect::<Vec<_>>();
+    sorted.sort_unstable();
+
+    let mut preimage = Vec::with_capacity(context.len() + (sorted.len() * 2));
+    preimage.extend(context.as_bytes());
+    for l in sorted {
+      preimage.extend(l.to_be_bytes());
+      let contribution = &contributions[&l];
+      preimage.extend(u32::try_from(contribution.len()).unwrap().to_be_bytes());
+      preimage.extend(contribution);
+    }
+
+    C::F_to_bytes(&C::hash_to_F(&preimage))
+  }
+
+  /// Processes every contributor's broadcast in one pass: batch-verifies all proofs of knowledge,
+  /// verifies this participant's own share against each contributor's broadcast commitments, and
+  /// derives the group key and verification shares, all without a second interactive round.
+  pub fn complete<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    params: MultisigParams,
+    context: &str,
+    our_commitments: Vec<u8>,
+    our_share: C::F,
+    commitments: HashMap<u16, Vec<u8>>,
+    mut shares: HashMap<u16, Vec<u8>>,
+  ) -> Result<MultisigKeys<C>, FrostError> {
+    // The communication keys published alongside these commitments aren't used here, as this
+    // mode's shares are dealt plaintext rather than encrypted to them; see `generate_contribution`
+    let (commitments, _comm_keys) =
+      super::verify_r1::<R, C>(rng, &params, context, our_commitments, commitments)?;
+
+    super::validate_map(
+      &mut shares,
+      &(1 ..= params.n()).into_iter().collect::<Vec<_>>(),
+      (params.i(), C::F_to_bytes(&our_share)),
+    )?;
+
+    let mut parsed_shares = HashMap::new();
+    for (l, share) in shares {
+      parsed_shares
+        .insert(l, C::F_from_slice(&share).map_err(|_| FrostError::InvalidShare(l))?);
+    }
+
+    super::finalize_shares(params, parsed_shares, commitments)
+  }
+}
+
+/// A trusted-dealer key generation path, for deployments which don't need (or want) the full
+/// interactive DKG: testing, migrating existing key material, or deliberate single-operator
+/// custody. A single dealer samples (or is handed) the group secret, secret-shares it via one
+/// degree-`(t-1)` polynomial, and hands each participant its share alongside the VSS commitments,
+/// without any interactive round.
+///
+/// Because the dealer could be faulty or malicious, a recipient shouldn't simply trust the share
+/// it's handed: [`verify_dealt_share`] checks it against the published commitments exactly as
+/// `complete_r2` checks a DKG-derived share against its sender's commitments, and
+/// [`complete_dealt_share`] then derives `group_key` and `verification_shares` from those same
+/// commitments, so the resulting `MultisigKeys` is byte-for-byte interchangeable with DKG output.
+pub mod trusted_dealer {
+  use std::collections::HashMap;
+
+  use rand_core::{RngCore, CryptoRng};
+  use ff::{Field, PrimeField};
+
+  use crate::{Curve, MultisigParams, MultisigKeys, FrostError};
+
+  /// A dealer's output: the serialized VSS commitments to broadcast to every participant, and
+  /// each participant's individual share, to be sent to them over a confidential channel. Unlike
+  /// `StateMachine`, the dealer already knows every share, so there's no round in which to derive
+  /// per-recipient encryption keys the way `generate_key_r1`/`generate_key_r2` do
+  pub struct DealerOutput<C: Curve> {
+    pub commitments: Vec<u8>,
+    pub shares: HashMap<u16, C::F>,
+  }
+
+  /// Deals a fresh (or provided) secret to `n` participants with a threshold of `t`.
+  pub fn deal<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    t: u16,
+    n: u16,
+    secret: Option<C::F>,
+  ) -> DealerOutput<C> {
+    let mut coefficients = Vec::with_capacity(usize::from(t));
+    coefficients.push(secret.unwrap_or_else(|| C::F::random(&mut *rng)));
+    for _ in 1 .. t {
+      coefficients.push(C::F::random(&mut *rng));
+    }
+
+    let mut serialized = Vec::with_capacity(C::G_len() * usize::from(t));
+    for coefficient in &coefficients {
+      serialized.extend(&C::G_to_bytes(&(C::generator_table() * *coefficient)));
+    }
+
+    let mut shares = HashMap::new();
+    for l in 1 ..= n {
+      shares.insert(l, super::polynomial(&coefficients, l));
+    }
+
+    DealerOutput { commitments: serialized, shares }
+  }
+
+  // Parses the dealer's serialized commitments into group elements, in ascending degree order
+  fn parse_commitments<C: Curve>(
+    params: &MultisigParams,
+    commitments: &[u8],
+  ) -> Result<Vec<C::G>, FrostError> {
+    let t = usize::from(params.t());
+    let mut parsed = Vec::with_capacity(t);
+    for c in 0 .. t {
+      parsed.push(
+        C::G_from_slice(&commitments[(c * C::G_len()) .. ((c + 1) * C::G_len())])
+          .map_err(|_| FrostError::InvalidCommitment(params.i()))?,
+      );
+    }
+    Ok(parsed)
+  }
+
+  /// Verifies a dealt share against the dealer's published commitments, exactly as `complete_r2`
+  /// verifies a DKG-derived share against its sender's commitments, guarding a recipient against a
+  /// faulty or malicious dealer before it builds a `MultisigKeys` around the share.
+  pub fn verify_dealt_share<C: Curve>(
+    params: &MultisigParams,
+    commitments: &[u8],
+    share: C::F,
+  ) -> Result<Vec<C::G>, FrostError> {
+    let parsed = parse_commitments::<C>(params, commitments)?;
+
+    let i_scalar = C::F::from(u64::from(params.i()));
+    let mut exp = C::F::one();
+    let mut exps = Vec::with_capacity(parsed.len());
+    for _ in 0 .. parsed.len() {
+      exps.push(exp);
+      exp *= i_scalar;
+    }
+
+    if C::multiexp_vartime(&exps, &parsed) != (C::generator_table() * share) {
+      Err(FrostError::InvalidCommitment(params.i()))?;
+    }
+
+    Ok(parsed)
+  }
+
+  /// Builds this participant's `MultisigKeys` from a dealt share already checked by
+  /// [`verify_dealt_share`], deriving `group_key` and `verification_shares` from the same
+  /// commitments.
+  pub fn complete_dealt_share<C: Curve>(
+    params: MultisigParams,
+    secret_share: C::F,
+    commitments: Vec<C::G>,
+  ) -> MultisigKeys<C> {
+    let group_key = commitments[0];
+
+    let mut verification_shares = HashMap::new();
+    for l in 1 ..= params.n() {
+      let l_scalar = C::F::from(u64::from(l));
+      let mut exp = C::F::one();
+      let mut exps = Vec::with_capacity(commitments.len());
+      for _ in 0 .. commitments.len() {
+        exps.push(exp);
+        exp *= l_scalar;
+      }
+      verification_shares.insert(l, C::multiexp_vartime(&exps, &commitments));
+    }
+
+    MultisigKeys { params, secret_share, group_key, verification_shares, offset: None }
+  }
+
+  /// Deals a fresh (or provided) secret to `n` participants and returns each participant's
+  /// finished `MultisigKeys` in one call, wiring `deal`/`verify_dealt_share`/`complete_dealt_share`
+  /// together so a caller trusted to see every share (e.g. a test harness or a single operator
+  /// standing up a multisig for itself) doesn't have to re-derive that plumbing by hand.
+  pub fn keygen_with_dealer<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    t: u16,
+    n: u16,
+    secret: Option<C::F>,
+  ) -> Result<HashMap<u16, MultisigKeys<C>>, FrostError> {
+    let DealerOutput { commitments, shares } = deal::<_, C>(rng, t, n, secret);
+
+    let mut keys = HashMap::new();
+    for (l, share) in shares {
+      let params = MultisigParams::new(t, n, l)?;
+      let parsed = verify_dealt_share::<C>(&params, &commitments, share)?;
+      keys.insert(l, complete_dealt_share::<C>(params, share, parsed));
+    }
+    Ok(keys)
+  }
+}
+
+/// A complaint-and-justification subsystem for localizing and agreeing on faulty participants
+/// during the interactive DKG.
+///
+/// `verify_r1` already falls back from batch verification to naming a single participant whose
+/// proof of knowledge is invalid, and `complete_r2` returns `InvalidShare`/`InvalidCommitment` when
+/// a dealt share fails its commitment check, but neither gives honest parties a way to *agree* on
+/// whom to exclude, which matters once this runs alongside consensus rather than a single process.
+/// This fills that gap: a participant who receives an invalid share broadcasts a [`Complaint`]
+/// naming the sender; the accused (or anyone holding the disputed share) can clear it with a
+/// [`Justification`], which every party re-checks via [`verify_justification`] against the
+/// accused's published commitments using the same relation `complete_r2` uses. Once all
+/// complaints are resolved, [`resolve_faulty`] gives the agreed faulty set, and
+/// [`finalize_excluding_faulty`] recomputes `group_key` and `verification_shares` over the
+/// remaining qualified participants.
+pub mod complaints {
+  use std::collections::{HashMap, HashSet};
+
+  use ff::{Field, PrimeField};
+
+  use crate::{Curve, MultisigParams, MultisigKeys, FrostError};
+
+  /// An accusation that `accused` dealt `complainant` an invalid share. It carries no signature;
+  /// authorship rests on the channel it's broadcast over.
+  pub struct Complaint {
+    pub complainant: u16,
+    pub accused: u16,
+  }
+
+  /// A revealed share clearing a [`Complaint`] against `accused`, to be re-checked by every party
+  /// against `accused`'s published commitments.
+  pub struct Justification<C: Curve> {
+    pub accused: u16,
+    pub share: C::F,
+  }
+
+  // The same relation complete_r2 checks every decrypted share against: does this share lie on
+  // the accused's committed polynomial, evaluated at the complainant's index?
+  fn share_matches_commitments<C: Curve>(
+    complainant: u16,
+    share: C::F,
+    commitments: &[C::G],
+  ) -> bool {
+    let i_scalar = C::F::from(u64::from(complainant));
+    let mut exp = C::F::one();
+    let mut exps = Vec::with_capacity(commitments.len());
+    for _ in 0 .. commitments.len() {
+      exps.push(exp);
+      exp *= i_scalar;
+    }
+    C::multiexp_vartime(&exps, commitments) == (C::generator_table() * share)
+  }
+
+  /// Re-checks a justification against the accused's broadcast commitments. `Ok(true)` clears the
+  /// complaint; `Ok(false)` leaves it standing (the justification itself doesn't match the
+  /// commitments, which is as damning for the accused as never justifying at all).
+  pub fn verify_justification<C: Curve>(
+    complaint: &Complaint,
+    justification: &Justification<C>,
+    accused_commitments: &[C::G],
+  ) -> Result<bool, FrostError> {
+    if complaint.accused != justification.accused {
+      Err(FrostError::InternalError(
+        "justification doesn't match the complaint it's meant to clear".to_string(),
+      ))?;
+    }
+    Ok(share_matches_commitments::<C>(
+      complaint.complainant,
+      justification.share,
+      accused_commitments,
+    ))
+  }
+
+  /// Deterministically resolves every filed complaint into the agreed faulty set: a participant
+  /// is faulty iff at least one complaint filed against them went unjustified. `justified` holds
+  /// one `(complainant, accused)` pair per [`Complaint`] that had a valid [`Justification`], per
+  /// [`verify_justification`]. This is scoped per-complaint rather than per-accused so that an
+  /// accused participant justifying one complaint against them doesn't clear a different,
+  /// unjustified complaint against that same participant — a dealer who deals a genuinely bad
+  /// share to one victim can't launder it by justifying an unrelated complaint from another.
+  pub fn resolve_faulty(
+    complaints: &[Complaint],
+    justified: &HashSet<(u16, u16)>,
+  ) -> HashSet<u16> {
+    complaints
+      .iter()
+      .filter(|complaint| !justified.contains(&(complaint.complainant, complaint.accused)))
+      .map(|complaint| complaint.accused)
+      .collect()
+  }
+
+  /// Recomputes `group_key` and `verification_shares` over the qualified set (every contributor
+  /// minus the agreed faulty set), and sums only the qualified set's dealt shares into the secret
+  /// share, so an excluded contributor's polynomial has no remaining influence on the output key.
+  pub fn finalize_excluding_faulty<C: Curve>(
+    params: MultisigParams,
+    shares: HashMap<u16, C::F>,
+    commitments: HashMap<u16, Vec<C::G>>,
+    faulty: &HashSet<u16>,
+  ) -> MultisigKeys<C> {
+    let qualified: Vec<u16> = commitments.keys().copied().filter(|l| !faulty.contains(l)).collect();
+
+    let mut secret_share = C::F::zero();
+    for &l in &qualified {
+      secret_share += shares[&l];
+    }
+
+    let mut verification_shares = HashMap::new();
+    for l in 1 ..= params.n() {
+      let mut exps = vec![];
+      let mut cs = vec![];
+      for &i in &qualified {
+        let mut exp = C::F::one();
+        for j in 0 .. usize::from(params.t()) {
+          exps.push(exp);
+          cs.push(commitments[&i][j]);
+          exp *= C::F::from(u64::from(l));
+        }
+      }
+      verification_shares.insert(l, C::multiexp_vartime(&exps, &cs));
+    }
+
+    let group_key = qualified.iter().map(|l| commitments[l][0]).sum();
+
+    MultisigKeys { params, secret_share, group_key, verification_shares, offset: None }
+  }
+}
+
+/// A proactive resharing protocol which rotates an existing `MultisigKeys<C>` quorum onto a new
+/// participant set (and, if desired, a new threshold) without ever reconstructing `group_key`.
+///
+/// Exactly `old_params.t()` of the old committee's members (`helpers`) each deal a fresh
+/// degree-`(new_params.t() - 1)` zero-sharing to the new participant set, evaluating it at each
+/// new participant's index as [`super::StateMachine::generate_secret_shares`] does, though the
+/// dealt values are returned unencrypted. A zero-sharing alone would contribute
+/// nothing to the new key; what makes it carry the old secret forward is that each dealer `l`
+/// blinds its Lagrange-weighted old share `lagrange_l * secret_share_l` with its zero-sharing
+/// before sending, rather than dealing the blind on its own. Because `sum_{l in helpers}
+/// lagrange_l * secret_share_l` is exactly the Lagrange reconstruction of the old secret, and
+/// every zero-sharing's constant term vanishes, the new committee's shares sum to a sharing of the
+/// *same* secret under a fresh degree-`(new_params.t() - 1)` polynomial — recomputing
+/// `verification_shares` (and leaving `group_key` unchanged) is then identical to
+/// [`finalize_shares`].
+///
+/// Since a dealt share's own constant term is zero, [`verify_contribution`] can check the wire
+/// value against the old committee's already-public `verification_shares` (Lagrange-weighted) in
+/// place of a freshly transmitted constant-term commitment, provided the dealer's broadcast
+/// constant-term commitment is first confirmed to actually be the identity.
+pub mod reshare {
+  use std::collections::HashMap;
+
+  use rand_core::{RngCore, CryptoRng};
+  use ff::Field;
+  use group::Group;
+
+  use crate::{Curve, MultisigParams, MultisigKeys, FrostError};
+
+  // Computes the Lagrange coefficient L_l = prod_{j in helpers, j != l} (0 - j) / (l - j), used to
+  // weight dealer l's old share so that sum_{l in helpers} L_l * secret_share_l reconstructs the
+  // secret at 0
+  fn lagrange_coefficient<C: Curve>(l: u16, helpers: &[u16]) -> C::F {
+    let l_scalar = C::F::from(u64::from(l));
+
+    let mut numerator = C::F::one();
+    let mut denominator = C::F::one();
+    for &j in helpers {
+      if j == l {
+        continue;
+      }
+      let j_scalar = C::F::from(u64::from(j));
+      numerator *= -j_scalar;
+      denominator *= l_scalar - j_scalar;
+    }
+    numerator * denominator.invert().unwrap()
+  }
+
+  /// One dealer's contribution: the serialized commitments to its zero-sharing (broadcast to every
+  /// new participant) and the per-recipient wire values, each of which blinds the dealer's
+  /// Lagrange-weighted old share with that zero-sharing.
+  pub struct Contribution<C: Curve> {
+    pub commitments: Vec<u8>,
+    pub shares: HashMap<u16, C::F>,
+  }
+
+  /// Step 1: dealer `l` (one of the `old_params.t()` parties in `helpers` reconstructing the old
+  /// secret) deals a fresh zero-sharing to the incoming committee described by `new_params`, which
+  /// may differ from the old committee in both `t` and `n`.
+  pub fn generate_contribution<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    old_keys: &MultisigKeys<C>,
+    new_params: &MultisigParams,
+    helpers: &[u16],
+  ) -> Contribution<C> {
+    let t = usize::from(new_params.t());
+
+    let mut coefficients = Vec::with_capacity(t);
+    coefficients.push(C::F::zero());
+    for _ in 1 .. t {
+      coefficients.push(C::F::random(&mut *rng));
+    }
+
+    let mut commitments = Vec::with_capacity(C::G_len() * t);
+    for coefficient in &coefficients {
+      commitments.extend(&C::G_to_bytes(&(C::generator_table() * *coefficient)));
+    }
+
+    let weighted_share =
+      lagrange_coefficient::<C>(old_keys.params.i(), helpers) * old_keys.secret_share;
+
+    let mut shares = HashMap::new();
+    for l in 1 ..= new_params.n() {
+      shares.insert(l, weighted_share + super::polynomial(&coefficients, l));
+    }
+
+    Contribution { commitments, shares }
+  }
+
+  /// Step 2: verifies a dealt contribution from `l` before it's folded into the new share.
+  /// `old_verification_share` is `l`'s already-public verification share from the old quorum.
+  /// Returns the commitments to use in [`complete`], with the (verified-identity) constant term
+  /// replaced by `l`'s Lagrange-weighted contribution to `group_key`.
+  pub fn verify_contribution<C: Curve>(
+    new_params: &MultisigParams,
+    l: u16,
+    helpers: &[u16],
+    old_verification_share: C::G,
+    commitments: &[u8],
+    share: C::F,
+  ) -> Result<Vec<C::G>, FrostError> {
+    let t = usize::from(new_params.t());
+    let mut parsed = Vec::with_capacity(t);
+    for c in 0 .. t {
+      parsed.push(
+        C::G_from_slice(&commitments[(c * C::G_len()) .. ((c + 1) * C::G_len())])
+          .map_err(|_| FrostError::InvalidCommitment(l))?,
+      );
+    }
+
+    // This must be a genuine zero-sharing: the only value it's permitted to carry is the
+    // Lagrange-weighted old share blinded into the wire values below, not an arbitrary constant
+    if parsed[0] != C::G::identity() {
+      Err(FrostError::InvalidCommitment(l))?;
+    }
+    parsed[0] = old_verification_share * lagrange_coefficient::<C>(l, helpers);
+
+    let i_scalar = C::F::from(u64::from(new_params.i()));
+    let mut exp = C::F::one();
+    let mut exps = Vec::with_capacity(t);
+    for _ in 0 .. t {
+      exps.push(exp);
+      exp *= i_scalar;
+    }
+
+    if C::multiexp_vartime(&exps, &parsed) != (C::generator_table() * share) {
+      Err(FrostError::InvalidShare(l))?;
+    }
+
+    Ok(parsed)
+  }
+
+  /// Step 3: once the new party holds one [`verify_contribution`]-checked share and commitment
+  /// vector from each of the `t` dealers in `helpers`, sums them into the new secret share and
+  /// recomputes `verification_shares` and `group_key` for `new_params`.
+  pub fn complete<C: Curve>(
+    new_params: MultisigParams,
+    shares: HashMap<u16, C::F>,
+    commitments: HashMap<u16, Vec<C::G>>,
+  ) -> MultisigKeys<C> {
+    let mut secret_share = C::F::zero();
+    for (_, share) in shares {
+      secret_share += share;
+    }
+
+    let mut verification_shares = HashMap::new();
+    for l in 1 ..= new_params.n() {
+      let mut exps = vec![];
+      let mut cs = vec![];
+      for these_commitments in commitments.values() {
+        let mut exp = C::F::one();
+        for c in these_commitments {
+          exps.push(exp);
+          cs.push(*c);
+          exp *= C::F::from(u64::from(l));
+        }
+      }
+      verification_shares.insert(l, C::multiexp_vartime(&exps, &cs));
+    }
+
+    let group_key = commitments.values().map(|c| c[0]).sum();
+
+    MultisigKeys { params: new_params, secret_share, group_key, verification_shares, offset: None }
+  }
+}
+
+/// A threshold ElGamal encryption/decryption subsystem built atop a completed DKG's `group_key`
+/// and `verification_shares`, letting the same quorum back a decryption committee (private
+/// voting, sealed-bid auctions, ...) rather than only a signer.
+///
+/// [`encrypt`] is a standalone, non-interactive ElGamal encryption to `group_key`. Decryption is
+/// the threshold operation: each holder calls [`decryption_share`] to compute `c1 *
+/// secret_share_i`, plus a Chaum-Pedersen proof that this share and the already-public
+/// `verification_shares[i] = g * secret_share_i` were computed with the same exponent, against the
+/// two different bases `c1` and `g`. A combiner runs [`verify_decryption_share`] on each
+/// (localizing blame to any holder whose proof fails, same as `complete_r2` does for a DKG share),
+/// then [`decrypt`] Lagrange-interpolates the verified shares in the exponent to recover `c1 *
+/// secret` and subtract it from `c2`.
+pub mod elgamal {
+  use std::collections::HashMap;
+
+  use rand_core::{RngCore, CryptoRng};
+  use ff::{Field, PrimeField};
+  use group::Group;
+
+  use crate::{Curve, MultisigKeys, FrostError};
+
+  /// An ElGamal ciphertext under `group_key`: `c1 = g * r`, `c2 = message + (group_key * r)`.
+  #[allow(non_snake_case)]
+  pub struct Ciphertext<C: Curve> {
+    pub c1: C::G,
+    pub c2: C::G,
+  }
+
+  /// Encrypts `message` to `group_key`. `message` is a group element, as is standard for ElGamal
+  /// atop a group without a known-order subgroup decryption map back to a scalar.
+  pub fn encrypt<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    group_key: C::G,
+    message: C::G,
+  ) -> Ciphertext<C> {
+    let r = C::F::random(rng);
+    Ciphertext { c1: C::generator_table() * r, c2: message + (group_key * r) }
+  }
+
+  /// A share-holder's decryption share for a [`Ciphertext`]'s `c1`, with a Chaum-Pedersen proof of
+  /// discrete-log equality against its DKG `verification_shares` entry.
+  pub struct DecryptionShare<C: Curve> {
+    pub share: C::G,
+    pub challenge: C::F,
+    pub response: C::F,
+  }
+
+  // The Chaum-Pedersen proof's Fiat-Shamir challenge, binding the holder's index, both bases
+  // (g and c1), and both images (the verification share and the decryption share) so a proof
+  // can't be replayed against a different ciphertext or claimed on another holder's behalf
+  fn dleq_challenge<C: Curve>(
+    l: u16,
+    c1: C::G,
+    verification_share: C::G,
+    nonce_g: C::G,
+    nonce_c1: C::G,
+    share: C::G,
+  ) -> C::F {
+    let mut preimage = Vec::with_capacity(2 + (5 * C::G_len()));
+    preimage.extend(l.to_be_bytes());
+    preimage.extend(&C::G_to_bytes(&c1));
+    preimage.extend(&C::G_to_bytes(&verification_share));
+    preimage.extend(&C::G_to_bytes(&nonce_g));
+    preimage.extend(&C::G_to_bytes(&nonce_c1));
+    preimage.extend(&C::G_to_bytes(&share));
+    C::hash_to_F(&preimage)
+  }
+
+  /// Produces holder `keys.params().i()`'s decryption share for `ciphertext`, proving in
+  /// zero-knowledge that it was computed with the same `secret_share` backing its
+  /// `verification_shares` entry.
+  pub fn decryption_share<R: RngCore + CryptoRng, C: Curve>(
+    rng: &mut R,
+    keys: &MultisigKeys<C>,
+    ciphertext: &Ciphertext<C>,
+  ) -> DecryptionShare<C> {
+    let share = ciphertext.c1 * keys.secret_share;
+
+    // Chaum-Pedersen: prove knowledge of an exponent equating g * x to verification_shares[i] and
+    // c1 * x to share, without revealing x (secret_share)
+    let nonce = C::F::random(&mut *rng);
+    let nonce_g = C::generator_table() * nonce;
+    let nonce_c1 = ciphertext.c1 * nonce;
+
+    let challenge = dleq_challenge::<C>(
+      keys.params.i(),
+      ciphertext.c1,
+      keys.verification_shares[&keys.params.i()],
+      nonce_g,
+      nonce_c1,
+      share,
+    );
+    let response = nonce + (challenge * keys.secret_share);
+
+    DecryptionShare { share, challenge, response }
+  }
+
+  /// Verifies `l`'s decryption share against its already-public `verification_share`, localizing
+  /// blame to `l` if the accompanying Chaum-Pedersen proof doesn't hold.
+  pub fn verify_decryption_share<C: Curve>(
+    l: u16,
+    ciphertext: &Ciphertext<C>,
+    verification_share: C::G,
+    decryption_share: &DecryptionShare<C>,
+  ) -> Result<(), FrostError> {
+    let nonce_g = (C::generator_table() * decryption_share.response) -
+      (verification_share * decryption_share.challenge);
+    let nonce_c1 = (ciphertext.c1 * decryption_share.response) -
+      (decryption_share.share * decryption_share.challenge);
+
+    let challenge = dleq_challenge::<C>(
+      l,
+      ciphertext.c1,
+      verification_share,
+      nonce_g,
+      nonce_c1,
+      decryption_share.share,
+    );
+
+    if challenge != decryption_share.challenge {
+      Err(FrostError::InvalidShare(l))?;
+    }
+    Ok(())
+  }
+
+  // Lagrange coefficient for reconstructing the exponent at 0 from the holders in `set`
+  fn lagrange_coefficient<C: Curve>(l: u16, set: &[u16]) -> C::F {
+    let l_scalar = C::F::from(u64::from(l));
+
+    let mut numerator = C::F::one();
+    let mut denominator = C::F::one();
+    for &j in set {
+      if j == l {
+        continue;
+      }
+      let j_scalar = C::F::from(u64::from(j));
+      numerator *= -j_scalar;
+      denominator *= l_scalar - j_scalar;
+    }
+    numerator * denominator.invert().unwrap()
+  }
+
+  /// Combines `t` verified decryption shares to recover `message`, Lagrange-interpolating
+  /// `c1 * secret` in the exponent from the per-holder shares and subtracting it from `c2`.
+  pub fn decrypt<C: Curve>(ciphertext: &Ciphertext<C>, shares: &HashMap<u16, C::G>) -> C::G {
+    let holders: Vec<u16> = shares.keys().copied().collect();
+    let c1_to_secret: C::G =
+      holders.iter().map(|&l| shares[&l] * lagrange_coefficient::<C>(l, &holders)).sum();
+    ciphertext.c2 - c1_to_secret
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::{HashMap, HashSet};
+
+  use rand_core::OsRng;
+  use group::Group;
+
+  use dalek_ff_group::Ristretto as TestCurve;
+
+  use super::*;
+
+  // Runs the two-round DKG to completion among `n` parties with threshold `t`
+  fn key_gen(t: u16, n: u16) -> HashMap<u16, MultisigKeys<TestCurve>> {
+    let mut rng = OsRng;
+
+    let mut machines = HashMap::new();
+    let mut commitments = HashMap::new();
+    for i in 1 ..= n {
+      let params = MultisigParams::new(t, n, i).unwrap();
+      let mut machine = StateMachine::<TestCurve>::new(params, "dkg-test".to_string());
+      commitments.insert(i, machine.generate_coefficients(&mut rng).unwrap());
+      machines.insert(i, machine);
+    }
+
+    let mut all_shares = HashMap::new();
+    for (i, machine) in machines.iter_mut() {
+      let mut their_commitments = commitments.clone();
+      their_commitments.remove(i);
+      all_shares.insert(*i, machine.generate_secret_shares(&mut rng, their_commitments).unwrap());
+    }
+
+    let mut keys = HashMap::new();
+    for (i, machine) in machines.iter_mut() {
+      let mut shares = HashMap::new();
+      for (j, dealt) in &all_shares {
+        if j == i {
+          continue;
+        }
+        shares.insert(*j, dealt[i].clone());
+      }
+      keys.insert(*i, machine.complete(shares).unwrap());
+    }
+
+    keys
+  }
+
+  #[test]
+  fn repair_recovers_the_same_share() {
+    let (t, n) = (3, 5);
+    let keys = key_gen(t, n);
+
+    let target = 1;
+    let helpers = vec![2, 3, 4];
+
+    let splits: Vec<_> = helpers
+      .iter()
+      .map(|&l| {
+        repair::split_contribution::<_, TestCurve>(
+          &mut OsRng,
+          l,
+          target,
+          keys[&l].secret_share,
+          &helpers,
+        )
+      })
+      .collect();
+
+    let aggregates: Vec<_> =
+      helpers.iter().map(|&l| repair::aggregate_received::<TestCurve>(l, &splits)).collect();
+
+    let recovered = repair::reconstruct_share::<TestCurve>(
+      &aggregates,
+      keys[&target].verification_shares[&target],
+    )
+    .unwrap();
+
+    assert_eq!(recovered, keys[&target].secret_share);
+  }
+
+  #[test]
+  fn reshare_preserves_group_key() {
+    let (t, n) = (3, 5);
+    let keys = key_gen(t, n);
+
+    let helpers = vec![1, 2, 3];
+    let (new_t, new_n) = (2, 4);
+
+    let mut contributions = HashMap::new();
+    for &l in &helpers {
+      let new_params = MultisigParams::new(new_t, new_n, 1).unwrap();
+      contributions
+        .insert(l, reshare::generate_contribution::<_, TestCurve>(&mut OsRng, &keys[&l], &new_params, &helpers));
+    }
+
+    let mut new_keys = HashMap::new();
+    for i in 1 ..= new_n {
+      let new_params = MultisigParams::new(new_t, new_n, i).unwrap();
+
+      let mut shares = HashMap::new();
+      let mut commitments = HashMap::new();
+      for &l in &helpers {
+        let contribution = &contributions[&l];
+        let parsed = reshare::verify_contribution::<TestCurve>(
+          &new_params,
+          l,
+          &helpers,
+          keys[&l].verification_shares[&l],
+          &contribution.commitments,
+          contribution.shares[&i],
+        )
+        .unwrap();
+        shares.insert(l, contribution.shares[&i]);
+        commitments.insert(l, parsed);
+      }
+
+      new_keys.insert(i, reshare::complete::<TestCurve>(new_params, shares, commitments));
+    }
+
+    for i in 2 ..= new_n {
+      assert_eq!(new_keys[&i].group_key, new_keys[&1].group_key);
+    }
+    assert_eq!(new_keys[&1].group_key, keys[&1].group_key);
+  }
+
+  #[test]
+  fn elgamal_decrypt_reverses_encrypt() {
+    let (t, n) = (3, 5);
+    let keys = key_gen(t, n);
+
+    let message = <TestCurve as Curve>::G::random(&mut OsRng);
+    let ciphertext = elgamal::encrypt(&mut OsRng, keys[&1].group_key, message);
+
+    let holders = vec![1, 2, 3];
+    let mut shares = HashMap::new();
+    for &l in &holders {
+      let share = elgamal::decryption_share(&mut OsRng, &keys[&l], &ciphertext);
+      elgamal::verify_decryption_share::<TestCurve>(
+        l,
+        &ciphertext,
+        keys[&l].verification_shares[&l],
+        &share,
+      )
+      .unwrap();
+      shares.insert(l, share.share);
+    }
+
+    assert_eq!(elgamal::decrypt(&ciphertext, &shares), message);
+  }
+
+  // Regression test for a bug where justifying one complaint against a dealer cleared every
+  // complaint against them: two victims complain about the same dealer, only one is justified,
+  // and the dealer must still end up in the faulty set because of the other, unjustified one
+  #[test]
+  fn justifying_one_complaint_does_not_clear_another() {
+    let complaints = vec![
+      complaints::Complaint { complainant: 2, accused: 1 },
+      complaints::Complaint { complainant: 3, accused: 1 },
+    ];
+    let justified = HashSet::from([(2, 1)]);
+
+    let faulty = complaints::resolve_faulty(&complaints, &justified);
+    assert!(faulty.contains(&1));
+  }
+}